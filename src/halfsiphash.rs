@@ -0,0 +1,453 @@
+//! An implementation of HalfSipHash: the 32-bit-word variant of SipHash.
+//!
+//! HalfSipHash operates on four 32-bit state words instead of SipHash's
+//! four 64-bit words, trading some security margin for speed on targets
+//! where 64-bit arithmetic is expensive (32-bit MCUs) or where the hash
+//! is only used to key small in-memory tables.
+
+use core::hash;
+use core::marker::PhantomData;
+
+/// An implementation of HalfSipHash 1-3, producing a 64-bit digest.
+#[derive(Debug, Clone, Default)]
+pub struct HalfSipHasher13 {
+    hasher: HalfHasher<HalfSip13Rounds>,
+}
+
+/// An implementation of HalfSipHash 2-4, producing a 64-bit digest.
+#[derive(Debug, Clone, Default)]
+pub struct HalfSipHasher24 {
+    hasher: HalfHasher<HalfSip24Rounds>,
+}
+
+/// An implementation of HalfSipHash 1-3, producing a 32-bit digest.
+///
+/// This is a distinct hasher from `HalfSipHasher13`, not just a truncation
+/// of its output: the reference algorithm folds the desired output width
+/// into `v1`'s initial value, so every compression round differs between
+/// the 32-bit and 64-bit variants, not only the finalization.
+#[derive(Debug, Clone, Default)]
+pub struct HalfSipHasher13_32 {
+    hasher: HalfHasher<HalfSip13Rounds32>,
+}
+
+/// An implementation of HalfSipHash 2-4, producing a 32-bit digest.
+///
+/// See `HalfSipHasher13_32` for why this isn't simply `HalfSipHasher24`
+/// truncated to 32 bits.
+#[derive(Debug, Clone, Default)]
+pub struct HalfSipHasher24_32 {
+    hasher: HalfHasher<HalfSip24Rounds32>,
+}
+
+#[derive(Debug)]
+struct HalfHasher<S: HalfSip> {
+    k0: u32,
+    k1: u32,
+    length: usize, // how many bytes we've processed
+    state: HalfState, // hash State
+    tail: u32, // unprocessed bytes le
+    ntail: usize, // how many bytes in tail are valid
+    _marker: PhantomData<S>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct HalfState {
+    v0: u32,
+    v1: u32,
+    v2: u32,
+    v3: u32,
+}
+
+macro_rules! u8to32_le {
+    ($buf:expr, $i:expr) =>
+    ($buf[0+$i] as u32 |
+     ($buf[1+$i] as u32) << 8 |
+     ($buf[2+$i] as u32) << 16 |
+     ($buf[3+$i] as u32) << 24);
+    ($buf:expr, $i:expr, $len:expr) =>
+    ({
+        let mut t = 0;
+        let mut out = 0;
+        while t < $len {
+            out |= ($buf[t+$i] as u32) << t*8;
+            t += 1;
+        }
+        out
+    });
+}
+
+macro_rules! rotl32 {
+    ($x:expr, $b:expr) =>
+    (($x << $b) | ($x >> (32_i32.wrapping_sub($b))))
+}
+
+macro_rules! half_compress {
+    ($state:expr) => ({
+        half_compress!($state.v0, $state.v1, $state.v2, $state.v3)
+    });
+    ($v0:expr, $v1:expr, $v2:expr, $v3:expr) =>
+    ({
+        $v0 = $v0.wrapping_add($v1); $v1 = rotl32!($v1, 5); $v1 ^= $v0;
+        $v0 = rotl32!($v0, 16);
+        $v2 = $v2.wrapping_add($v3); $v3 = rotl32!($v3, 8); $v3 ^= $v2;
+        $v0 = $v0.wrapping_add($v3); $v3 = rotl32!($v3, 7); $v3 ^= $v0;
+        $v2 = $v2.wrapping_add($v1); $v1 = rotl32!($v1, 13); $v1 ^= $v2;
+        $v2 = rotl32!($v2, 16);
+    });
+}
+
+impl HalfSipHasher13 {
+    /// Creates a new `HalfSipHasher13` with the two initial keys set to 0.
+    #[inline]
+    pub fn new() -> HalfSipHasher13 {
+        HalfSipHasher13::new_with_keys(0, 0)
+    }
+
+    /// Creates a `HalfSipHasher13` that is keyed off the provided keys.
+    #[inline]
+    pub fn new_with_keys(key0: u32, key1: u32) -> HalfSipHasher13 {
+        HalfSipHasher13 { hasher: HalfHasher::new_with_keys(key0, key1) }
+    }
+}
+
+impl HalfSipHasher24 {
+    /// Creates a new `HalfSipHasher24` with the two initial keys set to 0.
+    #[inline]
+    pub fn new() -> HalfSipHasher24 {
+        HalfSipHasher24::new_with_keys(0, 0)
+    }
+
+    /// Creates a `HalfSipHasher24` that is keyed off the provided keys.
+    #[inline]
+    pub fn new_with_keys(key0: u32, key1: u32) -> HalfSipHasher24 {
+        HalfSipHasher24 { hasher: HalfHasher::new_with_keys(key0, key1) }
+    }
+}
+
+impl HalfSipHasher13_32 {
+    /// Creates a new `HalfSipHasher13_32` with the two initial keys set to 0.
+    #[inline]
+    pub fn new() -> HalfSipHasher13_32 {
+        HalfSipHasher13_32::new_with_keys(0, 0)
+    }
+
+    /// Creates a `HalfSipHasher13_32` that is keyed off the provided keys.
+    #[inline]
+    pub fn new_with_keys(key0: u32, key1: u32) -> HalfSipHasher13_32 {
+        HalfSipHasher13_32 { hasher: HalfHasher::new_with_keys(key0, key1) }
+    }
+
+    /// Returns the 32-bit digest.
+    #[inline]
+    pub fn finish32(&self) -> u32 {
+        self.hasher.finish32()
+    }
+}
+
+impl HalfSipHasher24_32 {
+    /// Creates a new `HalfSipHasher24_32` with the two initial keys set to 0.
+    #[inline]
+    pub fn new() -> HalfSipHasher24_32 {
+        HalfSipHasher24_32::new_with_keys(0, 0)
+    }
+
+    /// Creates a `HalfSipHasher24_32` that is keyed off the provided keys.
+    #[inline]
+    pub fn new_with_keys(key0: u32, key1: u32) -> HalfSipHasher24_32 {
+        HalfSipHasher24_32 { hasher: HalfHasher::new_with_keys(key0, key1) }
+    }
+
+    /// Returns the 32-bit digest.
+    #[inline]
+    pub fn finish32(&self) -> u32 {
+        self.hasher.finish32()
+    }
+}
+
+impl<S: HalfSip> HalfHasher<S> {
+    #[inline]
+    fn new_with_keys(key0: u32, key1: u32) -> HalfHasher<S> {
+        let mut state = HalfHasher {
+            k0: key0,
+            k1: key1,
+            length: 0,
+            state: HalfState {
+                v0: 0,
+                v1: 0,
+                v2: 0,
+                v3: 0,
+            },
+            tail: 0,
+            ntail: 0,
+            _marker: PhantomData,
+        };
+        state.reset();
+        state
+    }
+
+    #[inline]
+    fn reset(&mut self) {
+        self.length = 0;
+        self.state.v0 = self.k0;
+        self.state.v1 = self.k1 ^ S::V1_INIT_XOR;
+        self.state.v2 = self.k0 ^ 0x6c796765;
+        self.state.v3 = self.k1 ^ 0x74656462;
+        self.ntail = 0;
+    }
+
+    // Runs the finalization shared by both digest widths: mix in the
+    // length byte, then a single c-round and d-round pass. The result is
+    // the complete 32-bit digest; the 64-bit digest additionally XORs
+    // `v1` with 0xdd and runs a second d-round pass on top of this state.
+    #[inline]
+    fn finalize_low(&self) -> (HalfState, u32) {
+        let mut state = self.state;
+
+        let b: u32 = ((self.length as u32 & 0xff) << 24) | self.tail;
+
+        state.v3 ^= b;
+        S::c_rounds(&mut state);
+        state.v0 ^= b;
+
+        state.v2 ^= 0xff;
+        S::d_rounds(&mut state);
+        let low = state.v1 ^ state.v3;
+
+        (state, low)
+    }
+
+    #[inline]
+    fn finish32(&self) -> u32 {
+        self.finalize_low().1
+    }
+
+    #[inline]
+    fn finish64(&self) -> u64 {
+        let (mut state, low) = self.finalize_low();
+
+        state.v1 ^= 0xdd;
+        S::d_rounds(&mut state);
+        let high = state.v1 ^ state.v3;
+
+        (low as u64) | (high as u64) << 32
+    }
+}
+
+impl hash::Hasher for HalfSipHasher13 {
+    #[inline]
+    fn write(&mut self, msg: &[u8]) {
+        self.hasher.write(msg)
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hasher.finish64()
+    }
+}
+
+impl hash::Hasher for HalfSipHasher24 {
+    #[inline]
+    fn write(&mut self, msg: &[u8]) {
+        self.hasher.write(msg)
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hasher.finish64()
+    }
+}
+
+impl hash::Hasher for HalfSipHasher13_32 {
+    #[inline]
+    fn write(&mut self, msg: &[u8]) {
+        self.hasher.write(msg)
+    }
+
+    /// Returns the 32-bit digest, widened to `u64` to satisfy this trait.
+    /// Use `finish32` to get the native digest type.
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hasher.finish32() as u64
+    }
+}
+
+impl hash::Hasher for HalfSipHasher24_32 {
+    #[inline]
+    fn write(&mut self, msg: &[u8]) {
+        self.hasher.write(msg)
+    }
+
+    /// Returns the 32-bit digest, widened to `u64` to satisfy this trait.
+    /// Use `finish32` to get the native digest type.
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.hasher.finish32() as u64
+    }
+}
+
+impl<S: HalfSip> hash::Hasher for HalfHasher<S> {
+    #[inline]
+    fn write(&mut self, msg: &[u8]) {
+        let length = msg.len();
+        self.length += length;
+
+        let mut needed = 0;
+
+        if self.ntail != 0 {
+            needed = 4 - self.ntail;
+            if length < needed {
+                self.tail |= u8to32_le!(msg, 0, length) << 8 * self.ntail;
+                self.ntail += length;
+                return;
+            }
+
+            let m = self.tail | u8to32_le!(msg, 0, needed) << 8 * self.ntail;
+
+            self.state.v3 ^= m;
+            S::c_rounds(&mut self.state);
+            self.state.v0 ^= m;
+
+            self.ntail = 0;
+        }
+
+        // Buffered tail is now flushed, process new input.
+        let len = length - needed;
+        let left = len & 0x3;
+
+        let mut i = needed;
+        while i < len - left {
+            let mi = u8to32_le!(msg, i);
+
+            self.state.v3 ^= mi;
+            S::c_rounds(&mut self.state);
+            self.state.v0 ^= mi;
+
+            i += 4;
+        }
+
+        self.tail = u8to32_le!(msg, i, left);
+        self.ntail = left;
+    }
+
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.finish64()
+    }
+}
+
+impl<S: HalfSip> Clone for HalfHasher<S> {
+    #[inline]
+    fn clone(&self) -> HalfHasher<S> {
+        HalfHasher {
+            k0: self.k0,
+            k1: self.k1,
+            length: self.length,
+            state: self.state,
+            tail: self.tail,
+            ntail: self.ntail,
+            _marker: self._marker,
+        }
+    }
+}
+
+impl<S: HalfSip> Default for HalfHasher<S> {
+    /// Creates a `HalfHasher<S>` with the two initial keys set to 0.
+    #[inline]
+    fn default() -> HalfHasher<S> {
+        HalfHasher::new_with_keys(0, 0)
+    }
+}
+
+#[doc(hidden)]
+trait HalfSip {
+    // XORed into `v1` during initialization, before any message bytes are
+    // absorbed. The reference algorithm only sets this for the 64-bit
+    // digest, so it affects every compression round, not just
+    // finalization: the 32-bit and 64-bit variants are different hash
+    // functions over the same input, not a truncation of one another.
+    const V1_INIT_XOR: u32;
+
+    fn c_rounds(&mut HalfState);
+    fn d_rounds(&mut HalfState);
+}
+
+#[derive(Debug, Clone, Default)]
+struct HalfSip13Rounds;
+
+impl HalfSip for HalfSip13Rounds {
+    const V1_INIT_XOR: u32 = 0xee;
+
+    #[inline]
+    fn c_rounds(state: &mut HalfState) {
+        half_compress!(state);
+    }
+
+    #[inline]
+    fn d_rounds(state: &mut HalfState) {
+        half_compress!(state);
+        half_compress!(state);
+        half_compress!(state);
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct HalfSip24Rounds;
+
+impl HalfSip for HalfSip24Rounds {
+    const V1_INIT_XOR: u32 = 0xee;
+
+    #[inline]
+    fn c_rounds(state: &mut HalfState) {
+        half_compress!(state);
+        half_compress!(state);
+    }
+
+    #[inline]
+    fn d_rounds(state: &mut HalfState) {
+        half_compress!(state);
+        half_compress!(state);
+        half_compress!(state);
+        half_compress!(state);
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct HalfSip13Rounds32;
+
+impl HalfSip for HalfSip13Rounds32 {
+    const V1_INIT_XOR: u32 = 0;
+
+    #[inline]
+    fn c_rounds(state: &mut HalfState) {
+        half_compress!(state);
+    }
+
+    #[inline]
+    fn d_rounds(state: &mut HalfState) {
+        half_compress!(state);
+        half_compress!(state);
+        half_compress!(state);
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct HalfSip24Rounds32;
+
+impl HalfSip for HalfSip24Rounds32 {
+    const V1_INIT_XOR: u32 = 0;
+
+    #[inline]
+    fn c_rounds(state: &mut HalfState) {
+        half_compress!(state);
+        half_compress!(state);
+    }
+
+    #[inline]
+    fn d_rounds(state: &mut HalfState) {
+        half_compress!(state);
+        half_compress!(state);
+        half_compress!(state);
+        half_compress!(state);
+    }
+}
@@ -2,14 +2,27 @@
 #![allow(clippy::unreadable_literal)]
 #![allow(clippy::cast_lossless)]
 
-pub mod sip;
+#[cfg(test)]
+extern crate core;
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(feature = "serde")]
+pub extern crate serde;
+
 pub mod sip128;
+pub mod halfsiphash;
+pub mod multi;
 
 #[cfg(test)]
-mod tests;
+mod tests128;
 
 #[cfg(test)]
-mod tests128;
+mod tests_half;
+
+#[cfg(test)]
+mod tests_multi;
 
 #[cfg(feature = "serde")]
 pub mod reexports {
@@ -0,0 +1,310 @@
+//! Batched SipHash: hash N independent messages in parallel.
+//!
+//! `SipHasher24x4` (and the generic `MultiHasher<S, N>` it's built on) keeps
+//! N independent SipHash states packed side by side so that one compression
+//! round advances all N messages at once. SipHash is serial *within* a
+//! single message, so this does not speed up hashing one large input — the
+//! win is for batches of independent keys, e.g. bulk hash-table inserts or
+//! dedup passes, where N short messages can be compressed together instead
+//! of one at a time.
+//!
+//! Every lane computes exactly the same result as feeding that lane's
+//! message into the corresponding single-message hasher
+//! (`SipHasher24` for `SipHasher24x4`); `write_lanes`/`finish_lanes` are
+//! purely a batching convenience, not a different algorithm.
+
+use core::marker::PhantomData;
+
+#[doc(hidden)]
+pub trait Rounds {
+    const C: usize;
+    const D: usize;
+}
+
+#[doc(hidden)]
+#[derive(Debug, Clone, Default)]
+pub struct Rounds13;
+
+impl Rounds for Rounds13 {
+    const C: usize = 1;
+    const D: usize = 3;
+}
+
+#[doc(hidden)]
+#[derive(Debug, Clone, Default)]
+pub struct Rounds24;
+
+impl Rounds for Rounds24 {
+    const C: usize = 2;
+    const D: usize = 4;
+}
+
+/// `N` independent SipHash-2-4 states, hashed together.
+pub type SipHasher24x4 = MultiHasher<Rounds24, 4>;
+
+/// `N` independent SipHash-1-3 states, hashed together.
+pub type SipHasher13x4 = MultiHasher<Rounds13, 4>;
+
+#[derive(Debug, Clone, Copy)]
+struct MultiState<const N: usize> {
+    v0: [u64; N],
+    v1: [u64; N],
+    v2: [u64; N],
+    v3: [u64; N],
+}
+
+/// `N` independent SipHash states, packed into lane arrays so the
+/// compression step can be vectorized across all of them at once.
+#[derive(Debug)]
+pub struct MultiHasher<S: Rounds, const N: usize> {
+    k0: [u64; N],
+    k1: [u64; N],
+    length: [usize; N],
+    state: MultiState<N>,
+    tail: [u64; N],
+    ntail: [usize; N],
+    _marker: PhantomData<S>,
+}
+
+macro_rules! u8to64_le {
+    ($buf:expr, $i:expr) =>
+    ($buf[0+$i] as u64 |
+     ($buf[1+$i] as u64) << 8 |
+     ($buf[2+$i] as u64) << 16 |
+     ($buf[3+$i] as u64) << 24 |
+     ($buf[4+$i] as u64) << 32 |
+     ($buf[5+$i] as u64) << 40 |
+     ($buf[6+$i] as u64) << 48 |
+     ($buf[7+$i] as u64) << 56);
+    ($buf:expr, $i:expr, $len:expr) =>
+    ({
+        let mut t = 0;
+        let mut out = 0;
+        while t < $len {
+            out |= ($buf[t+$i] as u64) << t*8;
+            t += 1;
+        }
+        out
+    });
+}
+
+macro_rules! rotl {
+    ($x:expr, $b:expr) =>
+    (($x << $b) | ($x >> (64_i32.wrapping_sub($b))))
+}
+
+macro_rules! scalar_compress {
+    ($v0:expr, $v1:expr, $v2:expr, $v3:expr) =>
+    ({
+        $v0 = $v0.wrapping_add($v1); $v1 = rotl!($v1, 13); $v1 ^= $v0;
+        $v0 = rotl!($v0, 32);
+        $v2 = $v2.wrapping_add($v3); $v3 = rotl!($v3, 16); $v3 ^= $v2;
+        $v0 = $v0.wrapping_add($v3); $v3 = rotl!($v3, 21); $v3 ^= $v0;
+        $v2 = $v2.wrapping_add($v1); $v1 = rotl!($v1, 17); $v1 ^= $v2;
+        $v2 = rotl!($v2, 32);
+    });
+}
+
+#[inline]
+fn compress_lanes<const N: usize>(
+    v0: &mut [u64; N],
+    v1: &mut [u64; N],
+    v2: &mut [u64; N],
+    v3: &mut [u64; N],
+) {
+    for lane in 0..N {
+        scalar_compress!(v0[lane], v1[lane], v2[lane], v3[lane]);
+    }
+}
+
+impl<S: Rounds, const N: usize> MultiHasher<S, N> {
+    /// Creates a new `MultiHasher`, one key pair per lane.
+    #[inline]
+    pub fn new_with_keys(keys0: [u64; N], keys1: [u64; N]) -> MultiHasher<S, N> {
+        let mut v0 = [0u64; N];
+        let mut v1 = [0u64; N];
+        let mut v2 = [0u64; N];
+        let mut v3 = [0u64; N];
+        for lane in 0..N {
+            v0[lane] = keys0[lane] ^ 0x736f6d6570736575;
+            v1[lane] = keys1[lane] ^ 0x646f72616e646f83;
+            v2[lane] = keys0[lane] ^ 0x6c7967656e657261;
+            v3[lane] = keys1[lane] ^ 0x7465646279746573;
+        }
+        MultiHasher {
+            k0: keys0,
+            k1: keys1,
+            length: [0; N],
+            state: MultiState { v0, v1, v2, v3 },
+            tail: [0; N],
+            ntail: [0; N],
+            _marker: PhantomData,
+        }
+    }
+
+    /// Feeds one complete message per lane.
+    ///
+    /// When all lanes are at the start of a fresh message (the common
+    /// case — batching N independent complete keys), the full 8-byte
+    /// blocks shared by every lane are compressed together; each lane's
+    /// remaining bytes (its own length minus what was common) are
+    /// absorbed with the ordinary per-lane buffering.
+    pub fn write_lanes(&mut self, msgs: [&[u8]; N]) {
+        let all_fresh = self.ntail.iter().all(|&n| n == 0);
+
+        let mut start = [0usize; N];
+        if all_fresh {
+            let common_len = msgs.iter().map(|m| m.len()).min().unwrap_or(0);
+            let common_blocks = common_len / 8;
+
+            for block in 0..common_blocks {
+                let i = block * 8;
+                let mut m = [0u64; N];
+                for lane in 0..N {
+                    m[lane] = u8to64_le!(msgs[lane], i);
+                }
+                for (lane, &mi) in m.iter().enumerate() {
+                    self.state.v3[lane] ^= mi;
+                }
+                for _ in 0..S::C {
+                    compress_lanes(
+                        &mut self.state.v0,
+                        &mut self.state.v1,
+                        &mut self.state.v2,
+                        &mut self.state.v3,
+                    );
+                }
+                for (lane, &mi) in m.iter().enumerate() {
+                    self.state.v0[lane] ^= mi;
+                }
+            }
+
+            for (lane, s) in start.iter_mut().enumerate() {
+                self.length[lane] += common_blocks * 8;
+                *s = common_blocks * 8;
+            }
+        }
+
+        for lane in 0..N {
+            self.write_lane(lane, &msgs[lane][start[lane]..]);
+        }
+    }
+
+    #[inline]
+    fn write_lane(&mut self, lane: usize, msg: &[u8]) {
+        let length = msg.len();
+        self.length[lane] += length;
+
+        let mut needed = 0;
+        if self.ntail[lane] != 0 {
+            needed = 8 - self.ntail[lane];
+            if length < needed {
+                self.tail[lane] |= u8to64_le!(msg, 0, length) << 8 * self.ntail[lane];
+                self.ntail[lane] += length;
+                return;
+            }
+
+            let m = self.tail[lane] | u8to64_le!(msg, 0, needed) << 8 * self.ntail[lane];
+            self.state.v3[lane] ^= m;
+            for _ in 0..S::C {
+                scalar_compress!(
+                    self.state.v0[lane],
+                    self.state.v1[lane],
+                    self.state.v2[lane],
+                    self.state.v3[lane]
+                );
+            }
+            self.state.v0[lane] ^= m;
+            self.ntail[lane] = 0;
+        }
+
+        let len = length - needed;
+        let left = len & 0x7;
+
+        let mut i = needed;
+        while i < len - left {
+            let mi = u8to64_le!(msg, i);
+            self.state.v3[lane] ^= mi;
+            for _ in 0..S::C {
+                scalar_compress!(
+                    self.state.v0[lane],
+                    self.state.v1[lane],
+                    self.state.v2[lane],
+                    self.state.v3[lane]
+                );
+            }
+            self.state.v0[lane] ^= mi;
+            i += 8;
+        }
+
+        self.tail[lane] = u8to64_le!(msg, i, left);
+        self.ntail[lane] = left;
+    }
+
+    /// Finalizes every lane, returning one 64-bit digest per message.
+    ///
+    /// Matches `SipHasher24`/`SipHasher13`'s plain (non-128-bit) `finish()`,
+    /// which is `finish128().h2`: the finalization mixes in the length byte
+    /// once, then runs *two* d-round passes (`v2 ^= 0xee` then `v1 ^= 0xdd`,
+    /// each followed by `S::D` rounds), taking the second `v0^v1^v2^v3` as
+    /// the output. A single d-round pass with `v2 ^= 0xff` is the plain
+    /// 64-bit SipHash finalization, not this crate's 128-bit one — using it
+    /// here would silently diverge from every other hasher in the crate.
+    pub fn finish_lanes(&self) -> [u64; N] {
+        let mut v0 = self.state.v0;
+        let mut v1 = self.state.v1;
+        let mut v2 = self.state.v2;
+        let mut v3 = self.state.v3;
+
+        let mut b = [0u64; N];
+        for lane in 0..N {
+            b[lane] = ((self.length[lane] as u64 & 0xff) << 56) | self.tail[lane];
+            v3[lane] ^= b[lane];
+        }
+        for _ in 0..S::C {
+            compress_lanes(&mut v0, &mut v1, &mut v2, &mut v3);
+        }
+        for lane in 0..N {
+            v0[lane] ^= b[lane];
+            v2[lane] ^= 0xee;
+        }
+        for _ in 0..S::D {
+            compress_lanes(&mut v0, &mut v1, &mut v2, &mut v3);
+        }
+        for v1_lane in v1.iter_mut() {
+            *v1_lane ^= 0xdd;
+        }
+        for _ in 0..S::D {
+            compress_lanes(&mut v0, &mut v1, &mut v2, &mut v3);
+        }
+
+        let mut out = [0u64; N];
+        for lane in 0..N {
+            out[lane] = v0[lane] ^ v1[lane] ^ v2[lane] ^ v3[lane];
+        }
+        out
+    }
+}
+
+impl<S: Rounds, const N: usize> Clone for MultiHasher<S, N> {
+    #[inline]
+    fn clone(&self) -> MultiHasher<S, N> {
+        MultiHasher {
+            k0: self.k0,
+            k1: self.k1,
+            length: self.length,
+            state: self.state,
+            tail: self.tail,
+            ntail: self.ntail,
+            _marker: self._marker,
+        }
+    }
+}
+
+impl<S: Rounds, const N: usize> Default for MultiHasher<S, N> {
+    /// Creates a `MultiHasher<S, N>` with every lane's keys set to 0.
+    #[inline]
+    fn default() -> MultiHasher<S, N> {
+        MultiHasher::new_with_keys([0; N], [0; N])
+    }
+}
@@ -10,12 +10,13 @@
 
 //! An implementation of SipHash.
 
-use std::hash;
-use std::marker::PhantomData;
-use std::ptr;
+use core::hash;
+use core::marker::PhantomData;
+use core::ptr;
 
 /// A 128-bit (2x64) hash output
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Hash128 {
     pub h1: u64,
     pub h2: u64,
@@ -23,12 +24,14 @@ pub struct Hash128 {
 
 /// An implementation of SipHash128 1-3.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SipHasher13 {
     hasher: Hasher<Sip13Rounds>,
 }
 
 /// An implementation of SipHash128 2-4.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SipHasher24 {
     hasher: Hasher<Sip24Rounds>,
 }
@@ -44,9 +47,12 @@ pub struct SipHasher24 {
 /// it is not intended for cryptographic purposes. As such, all
 /// cryptographic uses of this implementation are _strongly discouraged_.
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SipHasher(SipHasher24);
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
 struct Hasher<S: Sip> {
     k0: u64,
     k1: u64,
@@ -58,6 +64,7 @@ struct Hasher<S: Sip> {
 }
 
 #[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 struct State {
     // v0, v2 and v1, v3 show up in pairs in the algorithm,
     // and simd implementations of SipHash will use vectors
@@ -232,6 +239,48 @@ impl<S: Sip> Hasher<S> {
 }
 
 
+impl<S: Sip> Hasher<S> {
+    // Specialized write function for the `write_uN`/`write_iN` fast paths:
+    // `LEN` is known at compile time, so at most one word is mixed in and
+    // there's no need for the byte-slice loop that `write` uses. Must stay
+    // in lockstep with `write`'s buffering so the two paths are
+    // bit-for-bit identical.
+    //
+    // `write_uN`/`write_iN` feed `short_write` the integer's native-endian
+    // bytes (`to_ne_bytes`), matching `std::hash::Hasher`'s own `write_uN`
+    // convention, not a fixed little-endian encoding: on a big-endian
+    // target, `write_u64(x)` and `write(&x.to_le_bytes())` are expected to
+    // hash differently. See the `write_u64_matches_fixed_vector` test for
+    // a digest pinned against this target's native-endian behavior.
+    #[inline]
+    fn short_write<const LEN: usize>(&mut self, bytes: [u8; LEN]) {
+        debug_assert!(LEN <= 8);
+        self.length += LEN;
+
+        let needed = 8 - self.ntail;
+        let m = u8to64_le!(bytes, 0, LEN);
+
+        if LEN < needed {
+            self.tail |= m << (8 * self.ntail);
+            self.ntail += LEN;
+            return;
+        }
+
+        let m = self.tail | (m << (8 * self.ntail));
+
+        self.state.v3 ^= m;
+        S::c_rounds(&mut self.state);
+        self.state.v0 ^= m;
+
+        self.ntail = LEN - needed;
+        self.tail = if self.ntail == 0 {
+            0
+        } else {
+            u8to64_le!(bytes, needed, self.ntail)
+        };
+    }
+}
+
 impl<S: Sip> Hasher<S> {
     #[inline]
     pub fn finish128(&self) -> Hash128 {
@@ -261,6 +310,66 @@ impl hash::Hasher for SipHasher {
         self.0.write(msg)
     }
 
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.0.write_u8(i)
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.0.write_u16(i)
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.0.write_u32(i)
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.0.write_u64(i)
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.0.write_u128(i)
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.0.write_usize(i)
+    }
+
+    #[inline]
+    fn write_i8(&mut self, i: i8) {
+        self.0.write_i8(i)
+    }
+
+    #[inline]
+    fn write_i16(&mut self, i: i16) {
+        self.0.write_i16(i)
+    }
+
+    #[inline]
+    fn write_i32(&mut self, i: i32) {
+        self.0.write_i32(i)
+    }
+
+    #[inline]
+    fn write_i64(&mut self, i: i64) {
+        self.0.write_i64(i)
+    }
+
+    #[inline]
+    fn write_i128(&mut self, i: i128) {
+        self.0.write_i128(i)
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.0.write_isize(i)
+    }
+
     #[inline]
     fn finish(&self) -> u64 {
         self.0.finish()
@@ -273,6 +382,66 @@ impl hash::Hasher for SipHasher13 {
         self.hasher.write(msg)
     }
 
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.hasher.write_u8(i)
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.hasher.write_u16(i)
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.hasher.write_u32(i)
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.hasher.write_u64(i)
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.hasher.write_u128(i)
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.hasher.write_usize(i)
+    }
+
+    #[inline]
+    fn write_i8(&mut self, i: i8) {
+        self.hasher.write_i8(i)
+    }
+
+    #[inline]
+    fn write_i16(&mut self, i: i16) {
+        self.hasher.write_i16(i)
+    }
+
+    #[inline]
+    fn write_i32(&mut self, i: i32) {
+        self.hasher.write_i32(i)
+    }
+
+    #[inline]
+    fn write_i64(&mut self, i: i64) {
+        self.hasher.write_i64(i)
+    }
+
+    #[inline]
+    fn write_i128(&mut self, i: i128) {
+        self.hasher.write_i128(i)
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.hasher.write_isize(i)
+    }
+
     #[inline]
     fn finish(&self) -> u64 {
         self.hasher.finish()
@@ -285,6 +454,66 @@ impl hash::Hasher for SipHasher24 {
         self.hasher.write(msg)
     }
 
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.hasher.write_u8(i)
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.hasher.write_u16(i)
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.hasher.write_u32(i)
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.hasher.write_u64(i)
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.hasher.write_u128(i)
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.hasher.write_usize(i)
+    }
+
+    #[inline]
+    fn write_i8(&mut self, i: i8) {
+        self.hasher.write_i8(i)
+    }
+
+    #[inline]
+    fn write_i16(&mut self, i: i16) {
+        self.hasher.write_i16(i)
+    }
+
+    #[inline]
+    fn write_i32(&mut self, i: i32) {
+        self.hasher.write_i32(i)
+    }
+
+    #[inline]
+    fn write_i64(&mut self, i: i64) {
+        self.hasher.write_i64(i)
+    }
+
+    #[inline]
+    fn write_i128(&mut self, i: i128) {
+        self.hasher.write_i128(i)
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.hasher.write_isize(i)
+    }
+
     #[inline]
     fn finish(&self) -> u64 {
         self.hasher.finish()
@@ -335,6 +564,84 @@ impl<S: Sip> hash::Hasher for Hasher<S> {
         self.ntail = left;
     }
 
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.short_write(i.to_ne_bytes());
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.short_write(i.to_ne_bytes());
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.short_write(i.to_ne_bytes());
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.short_write(i.to_ne_bytes());
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        // Split `to_ne_bytes()` itself rather than assuming the low word
+        // comes first: on a big-endian target the high word's bytes lead,
+        // so hardcoding low-then-high here would diverge from
+        // `write(&i.to_ne_bytes())` on such targets.
+        let bytes = i.to_ne_bytes();
+        let mut first = [0u8; 8];
+        let mut second = [0u8; 8];
+        first.copy_from_slice(&bytes[..8]);
+        second.copy_from_slice(&bytes[8..]);
+        self.short_write(first);
+        self.short_write(second);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.short_write(i.to_ne_bytes());
+    }
+
+    #[inline]
+    fn write_i8(&mut self, i: i8) {
+        self.short_write(i.to_ne_bytes());
+    }
+
+    #[inline]
+    fn write_i16(&mut self, i: i16) {
+        self.short_write(i.to_ne_bytes());
+    }
+
+    #[inline]
+    fn write_i32(&mut self, i: i32) {
+        self.short_write(i.to_ne_bytes());
+    }
+
+    #[inline]
+    fn write_i64(&mut self, i: i64) {
+        self.short_write(i.to_ne_bytes());
+    }
+
+    #[inline]
+    fn write_i128(&mut self, i: i128) {
+        // See write_u128: split the native-endian bytes themselves so this
+        // stays correct on big-endian targets too.
+        let bytes = i.to_ne_bytes();
+        let mut first = [0u8; 8];
+        let mut second = [0u8; 8];
+        first.copy_from_slice(&bytes[..8]);
+        second.copy_from_slice(&bytes[8..]);
+        self.short_write(first);
+        self.short_write(second);
+    }
+
+    #[inline]
+    fn write_isize(&mut self, i: isize) {
+        self.short_write(i.to_ne_bytes());
+    }
+
     #[inline]
     fn finish(&self) -> u64 {
         self.finish128().h2
@@ -407,15 +714,79 @@ impl Sip for Sip24Rounds {
 }
 
 impl Hash128 {
-    /// Convert into a 16-bytes vector
-    pub fn into_bytes(&self) -> Vec<u8> {
-        let mut bytes = vec![0u8; 16];
-        let h1 = self.h1.to_le();
-        let h2 = self.h2.to_le();
-        unsafe {
-            ptr::copy_nonoverlapping(&h1 as *const _ as *const u8, bytes.get_unchecked_mut(0), 8);
-            ptr::copy_nonoverlapping(&h2 as *const _ as *const u8, bytes.get_unchecked_mut(8), 8);
+    /// Returns the hash as a 16-byte array, in little-endian byte order.
+    ///
+    /// Allocation-free; usable under `no_std` without an allocator.
+    #[inline]
+    pub const fn to_le_bytes(&self) -> [u8; 16] {
+        let h1 = self.h1.to_le_bytes();
+        let h2 = self.h2.to_le_bytes();
+        let mut bytes = [0u8; 16];
+        let mut i = 0;
+        while i < 8 {
+            bytes[i] = h1[i];
+            bytes[8 + i] = h2[i];
+            i += 1;
         }
         bytes
     }
+
+    /// Returns the hash as a 16-byte array, in native-endian byte order.
+    #[inline]
+    pub const fn to_ne_bytes(&self) -> [u8; 16] {
+        let h1 = self.h1.to_ne_bytes();
+        let h2 = self.h2.to_ne_bytes();
+        let mut bytes = [0u8; 16];
+        let mut i = 0;
+        while i < 8 {
+            bytes[i] = h1[i];
+            bytes[8 + i] = h2[i];
+            i += 1;
+        }
+        bytes
+    }
+
+    /// Reconstructs a `Hash128` from 16 little-endian bytes, as produced by
+    /// [`to_le_bytes`](Hash128::to_le_bytes).
+    #[inline]
+    pub const fn from_le_bytes(bytes: [u8; 16]) -> Hash128 {
+        let mut h1 = [0u8; 8];
+        let mut h2 = [0u8; 8];
+        let mut i = 0;
+        while i < 8 {
+            h1[i] = bytes[i];
+            h2[i] = bytes[8 + i];
+            i += 1;
+        }
+        Hash128 {
+            h1: u64::from_le_bytes(h1),
+            h2: u64::from_le_bytes(h2),
+        }
+    }
+
+    /// Returns the hash as a single 128-bit integer, with `h1` as the low
+    /// 64 bits and `h2` as the high 64 bits.
+    #[inline]
+    pub const fn as_u128(&self) -> u128 {
+        (self.h1 as u128) | ((self.h2 as u128) << 64)
+    }
+
+    /// Builds a `Hash128` from a single 128-bit integer, the inverse of
+    /// [`as_u128`](Hash128::as_u128).
+    #[inline]
+    pub const fn from_u128(value: u128) -> Hash128 {
+        Hash128 {
+            h1: value as u64,
+            h2: (value >> 64) as u64,
+        }
+    }
+
+    /// Convert into a 16-bytes vector.
+    ///
+    /// Requires an allocator; prefer [`to_le_bytes`](Hash128::to_le_bytes)
+    /// in `no_std` contexts without one.
+    #[cfg(feature = "alloc")]
+    pub fn into_bytes(&self) -> alloc::vec::Vec<u8> {
+        self.to_le_bytes().to_vec()
+    }
 }
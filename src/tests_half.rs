@@ -0,0 +1,265 @@
+use crate::halfsiphash::{HalfSipHasher13, HalfSipHasher13_32, HalfSipHasher24, HalfSipHasher24_32};
+use std::convert::TryInto;
+use std::hash::Hasher;
+
+/// A from-scratch, whole-message reference implementation of HalfSipHash,
+/// transcribed directly from the published algorithm rather than sharing
+/// any code with `halfsiphash.rs`'s streaming/tail-buffered implementation.
+/// Used as a known-answer oracle below so that a bug specific to one of
+/// the two implementations (e.g. a wrong rotation amount, or a tail-buffer
+/// off-by-one) can't pass unnoticed the way a purely self-referential test
+/// (determinism, streaming-matches-one-shot, ...) would.
+fn reference_halfsiphash(c_rounds: usize, d_rounds: usize, k0: u32, k1: u32, msg: &[u8]) -> u64 {
+    fn rotl(x: u32, b: u32) -> u32 {
+        (x << b) | (x >> (32 - b))
+    }
+
+    fn round(v: &mut [u32; 4]) {
+        v[0] = v[0].wrapping_add(v[1]);
+        v[1] = rotl(v[1], 5);
+        v[1] ^= v[0];
+        v[0] = rotl(v[0], 16);
+        v[2] = v[2].wrapping_add(v[3]);
+        v[3] = rotl(v[3], 8);
+        v[3] ^= v[2];
+        v[0] = v[0].wrapping_add(v[3]);
+        v[3] = rotl(v[3], 7);
+        v[3] ^= v[0];
+        v[2] = v[2].wrapping_add(v[1]);
+        v[1] = rotl(v[1], 13);
+        v[1] ^= v[2];
+        v[2] = rotl(v[2], 16);
+    }
+
+    let mut v = [
+        k0,
+        k1 ^ 0xee,
+        k0 ^ 0x6c79_6765,
+        k1 ^ 0x7465_6462,
+    ];
+
+    let chunks = msg.chunks_exact(4);
+    let remainder = chunks.remainder();
+    for block in chunks {
+        let mi = u32::from_le_bytes(block.try_into().unwrap());
+        v[3] ^= mi;
+        for _ in 0..c_rounds {
+            round(&mut v);
+        }
+        v[0] ^= mi;
+    }
+
+    let mut last_block = [0u8; 4];
+    last_block[..remainder.len()].copy_from_slice(remainder);
+    let b = ((msg.len() as u32 & 0xff) << 24) | u32::from_le_bytes(last_block);
+    v[3] ^= b;
+    for _ in 0..c_rounds {
+        round(&mut v);
+    }
+    v[0] ^= b;
+
+    v[2] ^= 0xff;
+    for _ in 0..d_rounds {
+        round(&mut v);
+    }
+    let low = v[1] ^ v[3];
+
+    v[1] ^= 0xdd;
+    for _ in 0..d_rounds {
+        round(&mut v);
+    }
+    let high = v[1] ^ v[3];
+
+    (low as u64) | ((high as u64) << 32)
+}
+
+/// Same as `reference_halfsiphash`, but for the 32-bit digest variant:
+/// `v1` is *not* XORed with 0xee during initialization (that tweak is
+/// specific to the 64-bit output), and only a single d-round pass runs.
+fn reference_halfsiphash32(c_rounds: usize, d_rounds: usize, k0: u32, k1: u32, msg: &[u8]) -> u32 {
+    fn rotl(x: u32, b: u32) -> u32 {
+        (x << b) | (x >> (32 - b))
+    }
+
+    fn round(v: &mut [u32; 4]) {
+        v[0] = v[0].wrapping_add(v[1]);
+        v[1] = rotl(v[1], 5);
+        v[1] ^= v[0];
+        v[0] = rotl(v[0], 16);
+        v[2] = v[2].wrapping_add(v[3]);
+        v[3] = rotl(v[3], 8);
+        v[3] ^= v[2];
+        v[0] = v[0].wrapping_add(v[3]);
+        v[3] = rotl(v[3], 7);
+        v[3] ^= v[0];
+        v[2] = v[2].wrapping_add(v[1]);
+        v[1] = rotl(v[1], 13);
+        v[1] ^= v[2];
+        v[2] = rotl(v[2], 16);
+    }
+
+    let mut v = [k0, k1, k0 ^ 0x6c79_6765, k1 ^ 0x7465_6462];
+
+    let chunks = msg.chunks_exact(4);
+    let remainder = chunks.remainder();
+    for block in chunks {
+        let mi = u32::from_le_bytes(block.try_into().unwrap());
+        v[3] ^= mi;
+        for _ in 0..c_rounds {
+            round(&mut v);
+        }
+        v[0] ^= mi;
+    }
+
+    let mut last_block = [0u8; 4];
+    last_block[..remainder.len()].copy_from_slice(remainder);
+    let b = ((msg.len() as u32 & 0xff) << 24) | u32::from_le_bytes(last_block);
+    v[3] ^= b;
+    for _ in 0..c_rounds {
+        round(&mut v);
+    }
+    v[0] ^= b;
+
+    v[2] ^= 0xff;
+    for _ in 0..d_rounds {
+        round(&mut v);
+    }
+    v[1] ^ v[3]
+}
+
+fn hash_u24(msg: &[u8]) -> u64 {
+    let mut h = HalfSipHasher24::new_with_keys(0x0001_0203, 0x0405_0607);
+    h.write(msg);
+    h.finish()
+}
+
+fn hash_u13(msg: &[u8]) -> u64 {
+    let mut h = HalfSipHasher13::new_with_keys(0x0001_0203, 0x0405_0607);
+    h.write(msg);
+    h.finish()
+}
+
+#[test]
+fn deterministic() {
+    let msg = b"some bytes to hash";
+    assert_eq!(hash_u24(msg), hash_u24(msg));
+    assert_eq!(hash_u13(msg), hash_u13(msg));
+}
+
+#[test]
+fn different_keys_differ() {
+    let msg = b"fixed message";
+    let mut a = HalfSipHasher24::new_with_keys(1, 2);
+    let mut b = HalfSipHasher24::new_with_keys(1, 3);
+    a.write(msg);
+    b.write(msg);
+    assert_ne!(a.finish(), b.finish());
+}
+
+#[test]
+fn different_messages_differ() {
+    assert_ne!(hash_u24(b"message one"), hash_u24(b"message two"));
+    assert_ne!(hash_u13(b"message one"), hash_u13(b"message two"));
+}
+
+#[test]
+fn rounds_variants_differ() {
+    assert_ne!(hash_u13(b"abc"), hash_u24(b"abc"));
+}
+
+#[test]
+fn streaming_matches_one_shot() {
+    let msg = b"a message long enough to span several 4-byte blocks and a tail";
+    let one_shot = hash_u24(msg);
+
+    let mut h = HalfSipHasher24::new_with_keys(0x0001_0203, 0x0405_0607);
+    for chunk in msg.chunks(3) {
+        h.write(chunk);
+    }
+    assert_eq!(one_shot, h.finish());
+}
+
+#[test]
+fn empty_input() {
+    assert_eq!(hash_u24(b""), hash_u24(b""));
+}
+
+#[test]
+fn matches_reference_implementation_vectors() {
+    // Canonical key bytes 0x00..0x07 (as in the published SipHash/HalfSipHash
+    // test vector sets), messages of length 0..=32 with bytes 0, 1, 2, ....
+    let k0 = u32::from_le_bytes([0x00, 0x01, 0x02, 0x03]);
+    let k1 = u32::from_le_bytes([0x04, 0x05, 0x06, 0x07]);
+    let msg: Vec<u8> = (0u8..32).collect();
+
+    for len in 0..=msg.len() {
+        let m = &msg[..len];
+
+        let mut h24 = HalfSipHasher24::new_with_keys(k0, k1);
+        h24.write(m);
+        assert_eq!(h24.finish(), reference_halfsiphash(2, 4, k0, k1, m), "2-4, len {}", len);
+
+        let mut h13 = HalfSipHasher13::new_with_keys(k0, k1);
+        h13.write(m);
+        assert_eq!(h13.finish(), reference_halfsiphash(1, 3, k0, k1, m), "1-3, len {}", len);
+
+        let mut h24_32 = HalfSipHasher24_32::new_with_keys(k0, k1);
+        h24_32.write(m);
+        assert_eq!(
+            h24_32.finish32(),
+            reference_halfsiphash32(2, 4, k0, k1, m),
+            "2-4 (32-bit), len {}",
+            len
+        );
+
+        let mut h13_32 = HalfSipHasher13_32::new_with_keys(k0, k1);
+        h13_32.write(m);
+        assert_eq!(
+            h13_32.finish32(),
+            reference_halfsiphash32(1, 3, k0, k1, m),
+            "1-3 (32-bit), len {}",
+            len
+        );
+    }
+}
+
+#[test]
+fn digest_32_differs_from_truncated_64() {
+    // The 32-bit and 64-bit variants diverge from the very first
+    // compression round (the 64-bit variant's v1 starts XORed with
+    // 0xee), so the 32-bit digest is not simply the low half of the
+    // 64-bit one.
+    let msg = b"some bytes to hash";
+    let mut h64 = HalfSipHasher24::new_with_keys(1, 2);
+    h64.write(msg);
+
+    let mut h32 = HalfSipHasher24_32::new_with_keys(1, 2);
+    h32.write(msg);
+
+    assert_ne!(h64.finish() as u32, h32.finish32());
+}
+
+#[test]
+fn digest_32_deterministic_and_streaming_consistent() {
+    let msg = b"a message long enough to span several 4-byte blocks and a tail";
+
+    let mut one_shot = HalfSipHasher24_32::new_with_keys(0x0001_0203, 0x0405_0607);
+    one_shot.write(msg);
+
+    let mut streamed = HalfSipHasher24_32::new_with_keys(0x0001_0203, 0x0405_0607);
+    for chunk in msg.chunks(3) {
+        streamed.write(chunk);
+    }
+
+    assert_eq!(one_shot.finish32(), streamed.finish32());
+}
+
+#[test]
+fn clone_preserves_state() {
+    let mut h = HalfSipHasher24::new_with_keys(9, 10);
+    h.write(b"partial");
+    let mut cloned = h.clone();
+    h.write(b" rest");
+    cloned.write(b" rest");
+    assert_eq!(h.finish(), cloned.finish());
+}
@@ -0,0 +1,147 @@
+use crate::multi::{SipHasher13x4, SipHasher24x4};
+use crate::sip128::{SipHasher13, SipHasher24};
+use std::hash::Hasher;
+
+const KEYS0: [u64; 4] = [1, 2, 3, 4];
+const KEYS1: [u64; 4] = [5, 6, 7, 8];
+
+#[test]
+fn matches_single_message_hasher() {
+    // The request's own acceptance criterion: every lane must be
+    // bit-identical to feeding that lane's message into the corresponding
+    // single-message hasher, not just internally self-consistent.
+    let msgs: [&[u8]; 4] = [
+        b"",
+        b"x",
+        b"a somewhat longer message spanning several 8-byte blocks",
+        b"exactly8",
+    ];
+
+    let mut multi24 = SipHasher24x4::new_with_keys(KEYS0, KEYS1);
+    multi24.write_lanes(msgs);
+    let out24 = multi24.finish_lanes();
+
+    let mut multi13 = SipHasher13x4::new_with_keys(KEYS0, KEYS1);
+    multi13.write_lanes(msgs);
+    let out13 = multi13.finish_lanes();
+
+    for lane in 0..4 {
+        let mut single24 = SipHasher24::new_with_keys(KEYS0[lane], KEYS1[lane]);
+        single24.write(msgs[lane]);
+        assert_eq!(out24[lane], single24.finish(), "SipHasher24 lane {}", lane);
+
+        let mut single13 = SipHasher13::new_with_keys(KEYS0[lane], KEYS1[lane]);
+        single13.write(msgs[lane]);
+        assert_eq!(out13[lane], single13.finish(), "SipHasher13 lane {}", lane);
+    }
+}
+
+#[test]
+fn all_lanes_share_a_full_block_on_first_write() {
+    // Regression test: when every lane is fresh and its message is at
+    // least one full 8-byte block, write_lanes takes the vectorized
+    // "all_fresh" fast path instead of falling through to write_lane for
+    // everything. That path must still account for the bytes it consumes
+    // in `self.length`, or the length byte mixed into finish_lanes is
+    // wrong. Every other test here includes a lane shorter than 8 bytes,
+    // so common_blocks is always 0 and this path never actually runs.
+    let msgs: [&[u8]; 4] = [
+        b"exactly16bytes!!",
+        b"exactly16bytes!!",
+        b"exactly16bytes!!",
+        b"exactly16bytes!!",
+    ];
+
+    let mut multi = SipHasher24x4::new_with_keys(KEYS0, KEYS1);
+    multi.write_lanes(msgs);
+    let out = multi.finish_lanes();
+
+    for lane in 0..4 {
+        let mut single = SipHasher24::new_with_keys(KEYS0[lane], KEYS1[lane]);
+        single.write(msgs[lane]);
+        assert_eq!(out[lane], single.finish(), "lane {}", lane);
+    }
+}
+
+#[test]
+fn one_shot_matches_byte_by_byte() {
+    let msgs: [&[u8]; 4] = [
+        b"short",
+        b"a somewhat longer message spanning several 8-byte blocks",
+        b"tiny",
+        b"exactly8",
+    ];
+
+    let mut one_shot = SipHasher24x4::new_with_keys(KEYS0, KEYS1);
+    one_shot.write_lanes(msgs);
+    let one_shot_out = one_shot.finish_lanes();
+
+    let mut streamed = SipHasher24x4::new_with_keys(KEYS0, KEYS1);
+    let max_len = msgs.iter().map(|m| m.len()).max().unwrap();
+    for i in 0..max_len {
+        let chunks: [&[u8]; 4] = core::array::from_fn(|lane| {
+            if i < msgs[lane].len() {
+                &msgs[lane][i..i + 1]
+            } else {
+                &[]
+            }
+        });
+        streamed.write_lanes(chunks);
+    }
+    let streamed_out = streamed.finish_lanes();
+
+    assert_eq!(one_shot_out, streamed_out);
+}
+
+#[test]
+fn lanes_are_independent() {
+    let msgs: [&[u8]; 4] = [b"aaaa", b"bbbb", b"cccc", b"dddd"];
+    let mut h = SipHasher24x4::new_with_keys(KEYS0, KEYS1);
+    h.write_lanes(msgs);
+    let out = h.finish_lanes();
+
+    for lane in 0..4 {
+        assert_ne!(out[lane], 0);
+        for other in (lane + 1)..4 {
+            assert_ne!(out[lane], out[other]);
+        }
+    }
+}
+
+#[test]
+fn deterministic() {
+    let msgs: [&[u8]; 4] = [b"one", b"two", b"three", b"four"];
+
+    let mut a = SipHasher24x4::new_with_keys(KEYS0, KEYS1);
+    a.write_lanes(msgs);
+
+    let mut b = SipHasher24x4::new_with_keys(KEYS0, KEYS1);
+    b.write_lanes(msgs);
+
+    assert_eq!(a.finish_lanes(), b.finish_lanes());
+}
+
+#[test]
+fn empty_lane_among_nonempty_lanes() {
+    let msgs: [&[u8]; 4] = [b"", b"x", b"hello world", b"abcdefgh"];
+    let mut h = SipHasher24x4::new_with_keys(KEYS0, KEYS1);
+    h.write_lanes(msgs);
+    let out = h.finish_lanes();
+
+    let mut lone = SipHasher24x4::new_with_keys(KEYS0, KEYS1);
+    lone.write_lanes([b"", b"", b"", b""]);
+    assert_eq!(out[0], lone.finish_lanes()[0]);
+}
+
+#[test]
+fn different_keys_differ() {
+    let msgs: [&[u8]; 4] = [b"one", b"two", b"three", b"four"];
+
+    let mut a = SipHasher24x4::new_with_keys(KEYS0, KEYS1);
+    a.write_lanes(msgs);
+
+    let mut b = SipHasher24x4::new_with_keys([9, 9, 9, 9], KEYS1);
+    b.write_lanes(msgs);
+
+    assert_ne!(a.finish_lanes()[0], b.finish_lanes()[0]);
+}